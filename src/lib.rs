@@ -1,8 +1,21 @@
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+
 mod node;
+#[cfg(feature = "merkle")]
+mod merkle;
 
 use node::TrieNode;
+#[cfg(feature = "merkle")]
+pub use merkle::{Hasher, MerkleProof, ProofNode, verify_proof};
+#[cfg(all(feature = "merkle", feature = "sha2"))]
+pub use merkle::Sha256Hasher;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Trie<T> {
     children: Vec<TrieNode<T>>,
 }
@@ -15,6 +28,14 @@ impl<T> Trie<T> {
 
     /// Retrieves the value associated with `key` from the trie, if any.
     pub fn get(&self, key: &str) -> Option<&T> {
+        self.get_bytes(key.as_bytes())
+    }
+
+    /// Byte-oriented counterpart to `get`. The underlying trie is keyed on
+    /// bytes regardless of how it is inserted into, so this also works
+    /// for binary keys (IP addresses, hashes, ...) that aren't valid
+    /// UTF-8.
+    pub fn get_bytes(&self, key: &[u8]) -> Option<&T> {
         // Search every node for a match.
         for node in &self.children {
             let value = node.get(key);
@@ -27,10 +48,68 @@ impl<T> Trie<T> {
         None
     }
 
+    /// Retrieves a mutable reference to the value associated with `key`
+    /// from the trie, if any.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut T> {
+        self.get_mut_bytes(key.as_bytes())
+    }
+
+    /// Byte-oriented counterpart to `get_mut`.
+    pub fn get_mut_bytes(&mut self, key: &[u8]) -> Option<&mut T> {
+        // Search every node for a match.
+        for node in &mut self.children {
+            let value = node.get_mut(key);
+            if value.is_some() {
+                return value;
+            }
+        }
+
+        // No matching node found
+        None
+    }
+
+    /// Returns an `Entry` for in-place updates to the value associated
+    /// with `key`, inserting if the key is not already present. The slot
+    /// `key` belongs at is found with a single descent of the trie and
+    /// threaded through to `Entry`, rather than re-searching for it on
+    /// every `Entry` method call.
+    ///
+    /// Finding that slot may itself need to allocate a node for `key`
+    /// (e.g. splitting an existing node), so a vacant `Entry` that is
+    /// dropped without ever being filled can leave a harmless valueless
+    /// node behind; it's cleaned up the next time `key` is deleted, or
+    /// filled in by a later `entry` for `key`. A later `insert` of `key`
+    /// does not fill it in.
+    ///
+    /// ```ignore
+    /// *trie.entry("hits").or_insert(0) += 1;
+    /// ```
+    pub fn entry<S: Into<String>>(&mut self, key: S) -> Entry<'_, T> {
+        Entry { slot: self.get_or_create_slot(key.into().into_bytes()) }
+    }
+
+    /// Finds (or creates) the node for `key` and returns its value slot.
+    fn get_or_create_slot(&mut self, key: Vec<u8>) -> &mut Option<T> {
+        let index = self.children.iter().position(|node| node.prefix_match(&key));
+        match index {
+            Some(i) => self.children[i].get_or_create_mut(key),
+            None => {
+                let mut node = TrieNode::new();
+                node.key = key;
+                self.children.push(node);
+                &mut self.children.last_mut().expect("just inserted").value
+            }
+        }
+    }
+
     /// Inserts the given key-value pair into the trie.
     pub fn insert<S: Into<String>>(&mut self, key: S, value: T) {
-        let key = key.into();
+        self.insert_bytes(key.into().into_bytes(), value)
+    }
 
+    /// Byte-oriented counterpart to `insert`, for storing binary keys
+    /// that aren't necessarily valid UTF-8.
+    pub fn insert_bytes(&mut self, key: Vec<u8>, value: T) {
         // Empty trie
         if self.children.is_empty() {
             let mut trie_node = TrieNode::new();
@@ -54,16 +133,233 @@ impl<T> Trie<T> {
         }
     }
 
-    /// Deletes the node matching `key` from the trie. If
-    /// `key` does not represent a complete node, i.e. a node
-    /// with a value, nothing happens.
-    pub fn delete(&mut self, _key: &str) {}
+    /// Deletes the node matching `key` from the trie, returning the value
+    /// that was removed. If `key` does not represent a complete node, i.e.
+    /// a node with a value, no value is removed and `None` is returned; a
+    /// valueless node left at `key` is still pruned or merged away.
+    pub fn delete(&mut self, key: &str) -> Option<T> {
+        self.delete_bytes(key.as_bytes())
+    }
+
+    /// Byte-oriented counterpart to `delete`.
+    pub fn delete_bytes(&mut self, key: &[u8]) -> Option<T> {
+        let mut removed = None;
+        let mut remove_index = None;
+
+        for (i, node) in self.children.iter_mut().enumerate() {
+            if node.prefix_match(key) {
+                let (value, should_remove_node) = node.delete(key);
+                removed = value;
+                if should_remove_node {
+                    remove_index = Some(i);
+                }
+                break;
+            }
+        }
+
+        if let Some(i) = remove_index {
+            self.children.remove(i);
+        }
+
+        removed
+    }
+
+    /// Returns the values stored at every node along the path to `key`
+    /// whose accumulated key is a prefix of `key`, in order of increasing
+    /// prefix length. Useful for routing tables, where more than one
+    /// registered prefix may match a given request.
+    ///
+    /// Only values are returned, so every match is included whether or
+    /// not its accumulated key is valid UTF-8.
+    pub fn find_prefixes(&self, key: &str) -> Vec<&T> {
+        self.find_prefixes_bytes(key.as_bytes())
+    }
+
+    /// Byte-oriented counterpart to `find_prefixes`.
+    pub fn find_prefixes_bytes(&self, key: &[u8]) -> Vec<&T> {
+        self.collect_prefix_matches(key)
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    /// Returns the longest registered prefix of `key` together with its
+    /// value, or `None` if no node along the path to `key` has a value.
+    ///
+    /// If the longest matching prefix isn't valid UTF-8 (which can only
+    /// happen with keys inserted via `insert_bytes`), `None` is returned,
+    /// even when a shorter matching prefix is valid UTF-8. Use
+    /// `find_longest_prefix_bytes` to get the match regardless of encoding.
+    pub fn find_longest_prefix(&self, key: &str) -> Option<(String, &T)> {
+        let (key, value) = self.find_longest_prefix_bytes(key.as_bytes())?;
+        String::from_utf8(key).ok().map(|key| (key, value))
+    }
+
+    /// Byte-oriented counterpart to `find_longest_prefix`.
+    pub fn find_longest_prefix_bytes(&self, key: &[u8]) -> Option<(Vec<u8>, &T)> {
+        self.collect_prefix_matches(key).pop()
+    }
+
+    /// Descends into the single child whose key is a prefix of `key`,
+    /// accumulating `(prefix, &value)` pairs at every node that carries a
+    /// value along the way.
+    fn collect_prefix_matches(&self, key: &[u8]) -> Vec<(Vec<u8>, &T)> {
+        let mut path = Vec::new();
+        let mut out = Vec::new();
+
+        for node in &self.children {
+            if node.prefix_match(key) {
+                node.collect_prefix_matches(key, &mut path, &mut out);
+                break;
+            }
+        }
+
+        out
+    }
+
+    /// Returns an iterator over `(key, &value)` pairs for every key stored
+    /// in the trie, keyed on bytes. Unlike `iter`, no entry is ever
+    /// silently dropped, so this also surfaces keys inserted via
+    /// `insert_bytes` that aren't valid UTF-8.
+    pub fn iter_bytes(&self) -> impl Iterator<Item = (Vec<u8>, &T)> {
+        let mut path = Vec::new();
+        let mut out = Vec::new();
+
+        for node in &self.children {
+            node.collect_entries(&mut path, &mut out);
+        }
+
+        out.into_iter()
+    }
+
+    /// Returns an iterator over `(key, &value)` pairs for every key stored
+    /// in the trie. Since each node only stores its own key fragment, the
+    /// full key is reconstructed by walking the tree depth-first.
+    ///
+    /// A key that isn't valid UTF-8 (for example one inserted via
+    /// `insert_bytes`) is silently skipped. Use `iter_bytes` to see
+    /// every entry regardless of key encoding.
+    pub fn iter(&self) -> impl Iterator<Item = (String, &T)> {
+        self.iter_bytes()
+            .filter_map(|(key, value)| String::from_utf8(key).ok().map(|key| (key, value)))
+    }
+
+    /// Returns an iterator over all keys stored in the trie, keyed on
+    /// bytes. See `iter_bytes` for why this never drops an entry.
+    pub fn keys_bytes(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.iter_bytes().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over all keys stored in the trie.
+    ///
+    /// See `iter` for why a non-UTF-8 key is silently skipped; use
+    /// `keys_bytes` to see every key regardless of encoding.
+    pub fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over all values stored in the trie. Unlike
+    /// `keys`/`iter`, a value is never dropped here: it doesn't matter
+    /// whether its key is valid UTF-8, since no key is returned.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.iter_bytes().map(|(_, value)| value)
+    }
+}
+
+#[cfg(feature = "merkle")]
+impl<T: AsRef<[u8]>> Trie<T> {
+    /// Recomputes the Merkle root digest committing to every key and
+    /// value currently stored in the trie, by rehashing every node from
+    /// scratch.
+    ///
+    /// Note: this does not cache digests between calls. A per-node cache
+    /// invalidated on `insert`/`delete` was the original design here, but
+    /// `H` is chosen per call rather than fixed to the trie, so a single
+    /// cached digest per node can't soundly be reused across calls with
+    /// different `Hasher`s. Caching would need either committing to one
+    /// `Hasher` for the trie's lifetime or keying the cache on `H`, and
+    /// wasn't worth the extra complexity here; callers that recompute
+    /// `root_hash` often should consider caching it themselves.
+    pub fn root_hash<H: Hasher>(&self) -> [u8; 32] {
+        let mut buf = Vec::new();
+        for child in &self.children {
+            buf.extend_from_slice(&child.digest::<H>());
+        }
+
+        H::hash(&buf)
+    }
+
+    /// Returns an inclusion proof for `key`, or `None` if `key` is not
+    /// present. Check it against a root digest with `verify_proof`.
+    pub fn prove<H: Hasher>(&self, key: &str) -> Option<MerkleProof> {
+        let key = key.as_bytes();
+
+        for (i, child) in self.children.iter().enumerate() {
+            if child.prefix_match(key) {
+                let mut proof = child.prove::<H>(key)?;
+
+                // The trie itself is a forest of root nodes with no key
+                // fragment of its own; fold the sibling roots in as one
+                // final proof step so the result matches `root_hash`.
+                let child_digests = self.children.iter().map(|c| c.digest::<H>()).collect();
+                proof.nodes.push(ProofNode {
+                    key: Vec::new(),
+                    value_digest: None,
+                    child_digests,
+                    path_child_index: Some(i),
+                });
+
+                return Some(proof);
+            }
+        }
+
+        None
+    }
+}
+
+/// A view into a single entry of a `Trie`, obtained from `Trie::entry`,
+/// which may either be vacant or occupied. Wraps the value slot found by
+/// the descent `Trie::entry` already performed, so every `Entry` method
+/// reuses it instead of searching the trie again.
+pub struct Entry<'a, T: 'a> {
+    slot: &'a mut Option<T>,
 }
 
-/// Returns the length of the common prefix shared between two strings.
-fn get_match_len(a: &str, b: &str) -> usize {
+impl<'a, T> Entry<'a, T> {
+    /// Ensures a value is present by inserting `default` if the entry is
+    /// vacant, then returns a mutable reference to the value.
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        self.or_insert_with(|| default)
+    }
+
+    /// Ensures a value is present by inserting the result of `default` if
+    /// the entry is vacant, then returns a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        if self.slot.is_none() {
+            *self.slot = Some(default());
+        }
+
+        self.slot.as_mut().expect("value was just inserted")
+    }
+
+    /// Applies `f` to the value in place if the entry is occupied, then
+    /// returns the entry so it can be chained into `or_insert`.
+    pub fn and_modify<F: FnOnce(&mut T)>(self, f: F) -> Self {
+        if let Some(value) = self.slot.as_mut() {
+            f(value);
+        }
+
+        self
+    }
+}
+
+/// Returns the length of the common prefix shared between two byte
+/// slices. The trie is keyed on raw bytes, not `char`s, so the length is
+/// counted in bytes, the same unit keys are sliced in; a match may end in
+/// the middle of a multi-byte UTF-8 sequence.
+fn get_match_len(a: &[u8], b: &[u8]) -> usize {
     let mut match_len = 0;
-    for (ac, bc) in a.chars().zip(b.chars()) {
+    for (ac, bc) in a.iter().zip(b.iter()) {
         if ac == bc {
             match_len += 1;
         } else {
@@ -86,7 +382,7 @@ mod test {
 
         let trie2 = Trie {
             children: vec![TrieNode {
-                               key: "data".to_string(),
+                               key: b"data".to_vec(),
                                value: Some(data),
                                children: Vec::new(),
                            }],
@@ -104,10 +400,10 @@ mod test {
 
         let trie2 = Trie {
             children: vec![TrieNode {
-                               key: "/".to_string(),
+                               key: b"/".to_vec(),
                                value: Some("data"),
                                children: vec![Box::new(TrieNode {
-                                                  key: "2".to_string(),
+                                                  key: b"2".to_vec(),
                                                   value: Some("data2"),
                                                   children: Vec::new(),
                                               })],
@@ -127,15 +423,15 @@ mod test {
 
         let trie2 = Trie {
             children: vec![TrieNode {
-                               key: "/".to_string(),
+                               key: b"/".to_vec(),
                                value: None,
                                children: vec![Box::new(TrieNode {
-                                                  key: "1".to_string(),
+                                                  key: b"1".to_vec(),
                                                   value: Some("Data"),
                                                   children: Vec::new(),
                                               }),
                                               Box::new(TrieNode {
-                                                  key: "2".to_string(),
+                                                  key: b"2".to_vec(),
                                                   value: Some("Data2"),
                                                   children: Vec::new(),
                                               })],
@@ -147,4 +443,267 @@ mod test {
         assert_eq!(trie.get("/1"), Some(&"Data"));
         assert_eq!(trie.get("/2"), Some(&"Data2"));
     }
+
+    #[test]
+    fn delete_split_point() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "Data");
+        trie.insert("/2", "Data2");
+
+        assert_eq!(trie.delete("/1"), Some("Data"));
+        assert_eq!(trie.get("/1"), None);
+        assert_eq!(trie.get("/2"), Some(&"Data2"));
+    }
+
+    #[test]
+    fn delete_merges_leaf() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "Data");
+        trie.insert("/2", "Data2");
+
+        assert_eq!(trie.delete("/2"), Some("Data2"));
+
+        // The split node had only one remaining child, so it should have
+        // merged back into a single "/1" node.
+        let trie2 = Trie {
+            children: vec![TrieNode {
+                               key: b"/1".to_vec(),
+                               value: Some("Data"),
+                               children: Vec::new(),
+                           }],
+        };
+        assert_eq!(trie, trie2);
+        assert_eq!(trie.get("/1"), Some(&"Data"));
+    }
+
+    #[test]
+    fn delete_missing_key() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "Data");
+
+        assert_eq!(trie.delete("/2"), None);
+        assert_eq!(trie.get("/1"), Some(&"Data"));
+    }
+
+    #[test]
+    fn find_longest_prefix_routing() {
+        let mut trie = Trie::new();
+        trie.insert("/", "root");
+        trie.insert("/1", "one");
+        trie.insert("/2", "two");
+
+        assert_eq!(trie.find_longest_prefix("/1"), Some(("/1".to_string(), &"one")));
+        assert_eq!(trie.find_longest_prefix("/1/nested"), Some(("/1".to_string(), &"one")));
+        assert_eq!(trie.find_longest_prefix("/3"), Some(("/".to_string(), &"root")));
+        assert_eq!(trie.find_longest_prefix("nope"), None);
+    }
+
+    #[test]
+    fn find_prefixes_routing() {
+        let mut trie = Trie::new();
+        trie.insert("/", "root");
+        trie.insert("/1", "one");
+
+        assert_eq!(trie.find_prefixes("/1"), vec![&"root", &"one"]);
+        assert_eq!(trie.find_prefixes("/2"), vec![&"root"]);
+        assert_eq!(trie.find_prefixes("nope"), Vec::<&&str>::new());
+    }
+
+    #[test]
+    fn iter_reconstructs_keys() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "one");
+        trie.insert("/2", "two");
+
+        let mut entries: Vec<_> = trie.iter().collect();
+        entries.sort();
+        assert_eq!(entries,
+                   vec![("/1".to_string(), &"one"), ("/2".to_string(), &"two")]);
+    }
+
+    #[test]
+    fn keys_and_values() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "one");
+        trie.insert("/2", "two");
+
+        let mut keys: Vec<_> = trie.keys().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["/1".to_string(), "/2".to_string()]);
+
+        let mut values: Vec<_> = trie.values().collect();
+        values.sort();
+        assert_eq!(values, vec![&"one", &"two"]);
+    }
+
+    #[test]
+    fn get_mut_updates_in_place() {
+        let mut trie = Trie::new();
+        trie.insert("/1", 1);
+
+        if let Some(value) = trie.get_mut("/1") {
+            *value += 1;
+        }
+
+        assert_eq!(trie.get("/1"), Some(&2));
+    }
+
+    #[test]
+    fn entry_or_insert_counts() {
+        let mut trie = Trie::new();
+
+        *trie.entry("hits").or_insert(0) += 1;
+        *trie.entry("hits").or_insert(0) += 1;
+
+        assert_eq!(trie.get("hits"), Some(&2));
+    }
+
+    #[test]
+    fn entry_or_insert_splits_an_existing_node() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "Data");
+
+        // "/2" shares only the "/" prefix with the existing "/1" node, so
+        // finding its slot has to split that node along the way.
+        trie.entry("/2").or_insert("Data2");
+
+        assert_eq!(trie.get("/1"), Some(&"Data"));
+        assert_eq!(trie.get("/2"), Some(&"Data2"));
+    }
+
+    #[test]
+    fn entry_or_insert_for_a_key_that_is_a_prefix_of_an_existing_one() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "Data1");
+
+        // "/" is a strict prefix of the existing "/1" node's key, so the
+        // split leaves no remainder to descend into; the new value
+        // belongs on the split-off node itself.
+        *trie.entry("/").or_insert("Root") = "Root";
+
+        assert_eq!(trie.get("/"), Some(&"Root"));
+        assert_eq!(trie.get("/1"), Some(&"Data1"));
+    }
+
+    #[test]
+    fn entry_and_modify() {
+        let mut trie = Trie::new();
+        trie.insert("hits", 1);
+
+        trie.entry("hits").and_modify(|v| *v += 1).or_insert(0);
+        trie.entry("misses").and_modify(|v| *v += 1).or_insert(1);
+
+        assert_eq!(trie.get("hits"), Some(&2));
+        assert_eq!(trie.get("misses"), Some(&1));
+    }
+
+    #[test]
+    fn byte_keys_need_not_be_utf8() {
+        let mut trie = Trie::new();
+        // A binary key: not valid UTF-8, unlike everything else in this
+        // file. Exercises the same split/merge logic as the &str API.
+        trie.insert_bytes(vec![192, 168, 0, 1], "router");
+        trie.insert_bytes(vec![192, 168, 0, 2], "printer");
+
+        assert_eq!(trie.get_bytes(&[192, 168, 0, 1]), Some(&"router"));
+        assert_eq!(trie.get_bytes(&[192, 168, 0, 2]), Some(&"printer"));
+        assert_eq!(trie.get_bytes(&[10, 0, 0, 1]), None);
+
+        assert_eq!(trie.delete_bytes(&[192, 168, 0, 1]), Some("router"));
+        assert_eq!(trie.get_bytes(&[192, 168, 0, 1]), None);
+        assert_eq!(trie.get_bytes(&[192, 168, 0, 2]), Some(&"printer"));
+    }
+
+    #[test]
+    fn iter_skips_non_utf8_keys_but_iter_bytes_does_not() {
+        let mut trie = Trie::new();
+        trie.insert_bytes(vec![192, 168, 0, 1], "router");
+        trie.insert("hello", "world");
+
+        // `iter` is &str-keyed, so the binary key can't be represented
+        // and is silently dropped.
+        assert_eq!(trie.iter().count(), 1);
+        assert_eq!(trie.values().count(), 2);
+
+        // `iter_bytes` surfaces every entry regardless of key encoding.
+        let mut entries: Vec<_> = trie.iter_bytes().collect();
+        entries.sort();
+        assert_eq!(entries,
+                   vec![(b"hello".to_vec(), &"world"), (vec![192, 168, 0, 1], &"router")]);
+
+        let mut keys: Vec<_> = trie.keys_bytes().collect();
+        keys.sort();
+        assert_eq!(keys, vec![b"hello".to_vec(), vec![192, 168, 0, 1]]);
+    }
+
+    #[test]
+    fn find_prefixes_bytes_matches_binary_keys() {
+        let mut trie = Trie::new();
+        trie.insert_bytes(vec![192, 168], "subnet");
+        trie.insert_bytes(vec![192, 168, 0, 1], "router");
+
+        assert_eq!(trie.find_prefixes_bytes(&[192, 168, 0, 1]),
+                   vec![&"subnet", &"router"]);
+        assert_eq!(trie.find_longest_prefix_bytes(&[192, 168, 0, 1]),
+                   Some((vec![192, 168, 0, 1], &"router")));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_test {
+    use super::Trie;
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut trie = Trie::new();
+        trie.insert("/", 0);
+        trie.insert("/1", 1);
+        trie.insert("/2", 2);
+
+        let json = serde_json::to_string(&trie).unwrap();
+        let restored: Trie<i32> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.get("/"), Some(&0));
+        assert_eq!(restored.get("/1"), Some(&1));
+        assert_eq!(restored.get("/2"), Some(&2));
+    }
+}
+
+#[cfg(all(test, feature = "merkle", feature = "sha2"))]
+mod merkle_test {
+    use super::{Trie, Sha256Hasher, verify_proof};
+
+    #[test]
+    fn proof_verifies_against_root_hash() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "one");
+        trie.insert("/2", "two");
+
+        let root_hash = trie.root_hash::<Sha256Hasher>();
+        let proof = trie.prove::<Sha256Hasher>("/1").expect("key is present");
+
+        assert!(verify_proof::<Sha256Hasher, _>(root_hash, b"/1", &"one", &proof));
+        assert!(!verify_proof::<Sha256Hasher, _>(root_hash, b"/1", &"wrong", &proof));
+    }
+
+    #[test]
+    fn missing_key_has_no_proof() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "one");
+
+        assert!(trie.prove::<Sha256Hasher>("/2").is_none());
+    }
+
+    #[test]
+    fn proof_does_not_verify_against_a_different_key() {
+        let mut trie = Trie::new();
+        trie.insert("/1", "one");
+
+        let root_hash = trie.root_hash::<Sha256Hasher>();
+        let proof = trie.prove::<Sha256Hasher>("/1").expect("key is present");
+
+        // A proof for "/1" must not also verify for some other key, even
+        // though the value and digest chain still hash up to root_hash.
+        assert!(!verify_proof::<Sha256Hasher, _>(root_hash, b"/999/does/not/exist", &"one", &proof));
+    }
 }
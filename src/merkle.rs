@@ -0,0 +1,107 @@
+//! Optional Merkle-hashing support for `Trie`, gated behind the `merkle`
+//! feature. Every node's digest commits to its key fragment, its value
+//! (when present), and the ordered digests of its children, so a single
+//! root digest commits to everything stored in the trie. This turns the
+//! structure into an authenticated key-value store: a party holding only
+//! `root_hash` can be convinced that a given key-value pair is present
+//! via a `MerkleProof`, without seeing the rest of the trie.
+
+/// Computes a fixed-size digest over arbitrary bytes. Implement this to
+/// plug in a different hash function than the default `Sha256Hasher`.
+pub trait Hasher {
+    /// Hashes `data` and returns the resulting digest.
+    fn hash(data: &[u8]) -> [u8; 32];
+}
+
+/// The default `Hasher`, backed by SHA-256.
+#[cfg(feature = "sha2")]
+pub struct Sha256Hasher;
+
+#[cfg(feature = "sha2")]
+impl Hasher for Sha256Hasher {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&hasher.finalize());
+        digest
+    }
+}
+
+/// One node's worth of witness data along the path from a trie's root to
+/// a proven key, ordered leaf-to-root.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProofNode {
+    /// This node's key fragment.
+    pub key: Vec<u8>,
+    /// This node's own value digest, if it has one. Only ever set on an
+    /// ancestor of the proven key; the proven node's value is supplied
+    /// separately to `verify_proof` and is never taken on trust.
+    pub value_digest: Option<[u8; 32]>,
+    /// Digests of this node's children, in order.
+    pub child_digests: Vec<[u8; 32]>,
+    /// Index into `child_digests` of the child that continues the path
+    /// to the proven key. `None` at the proven node itself.
+    pub path_child_index: Option<usize>,
+}
+
+/// An inclusion proof that a key-value pair is present in a trie with a
+/// given root digest. Produced by `Trie::prove`, checked by
+/// `verify_proof`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MerkleProof {
+    /// Nodes from the proven key up to the root, in that order.
+    pub nodes: Vec<ProofNode>,
+}
+
+/// Recomputes the path hash that `proof` describes for `value` and checks
+/// it against `root_hash`. `proof.nodes` is ordered leaf-to-root, so
+/// concatenating each node's key fragment from the root end down
+/// reconstructs the full key the proof was built for; this is checked
+/// against `key` before anything is hashed, so a proof for one key can't
+/// be replayed against another.
+pub fn verify_proof<H: Hasher, T: AsRef<[u8]>>(root_hash: [u8; 32],
+                                                key: &[u8],
+                                                value: &T,
+                                                proof: &MerkleProof)
+                                                -> bool {
+    let mut reconstructed_key = Vec::new();
+    for node in proof.nodes.iter().rev() {
+        reconstructed_key.extend_from_slice(&node.key);
+    }
+    if reconstructed_key != key {
+        return false;
+    }
+
+    let mut digest = H::hash(value.as_ref());
+
+    for node in &proof.nodes {
+        let mut buf = node.key.clone();
+
+        if node.path_child_index.is_none() {
+            // This is the proven node: fold in the freshly hashed value
+            // rather than anything supplied by the proof.
+            buf.extend_from_slice(&digest);
+        } else if let Some(value_digest) = node.value_digest {
+            buf.extend_from_slice(&value_digest);
+        }
+
+        let mut child_digests = node.child_digests.clone();
+        if let Some(i) = node.path_child_index {
+            match child_digests.get_mut(i) {
+                Some(slot) => *slot = digest,
+                None => return false,
+            }
+        }
+        for child_digest in &child_digests {
+            buf.extend_from_slice(child_digest);
+        }
+
+        digest = H::hash(&buf);
+    }
+
+    digest == root_hash
+}
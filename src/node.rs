@@ -1,13 +1,16 @@
 use super::get_match_len;
+#[cfg(feature = "merkle")]
+use super::merkle::{Hasher, MerkleProof, ProofNode};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TrieNode<T> {
     /// The key associated with this node.
-    key: Vec<u8>,
+    pub(crate) key: Vec<u8>,
     /// The value associated with this node, if any.
-    value: Option<T>,
+    pub(crate) value: Option<T>,
     /// All branches from this node
-    children: Vec<Box<TrieNode<T>>>,
+    pub(crate) children: Vec<Box<TrieNode<T>>>,
 }
 
 impl<T> TrieNode<T> {
@@ -59,12 +62,72 @@ impl<T> TrieNode<T> {
         None
     }
 
+    /// Retrieve a mutable reference to the value associated with `key`. If
+    /// the key is not found, `None` is returned.
+    pub fn get_mut(&mut self, key: &[u8]) -> Option<&mut T> {
+        let match_len = get_match_len(&self.key, key);
+        if match_len == self.key.len() {
+            if match_len == key.len() {
+                return self.value.as_mut();
+            } else {
+                let key = &key[match_len..];
+                return self.get_children_mut(key);
+            }
+        }
+        None
+    }
+
+    fn get_children_mut(&mut self, key: &[u8]) -> Option<&mut T> {
+        for child in &mut self.children {
+            if child.prefix_match(key) {
+                return child.get_mut(key);
+            }
+        }
+
+        None
+    }
+
     /// Returns true if this node's key and the given `key` share a common
     /// prefix.
     pub fn prefix_match(&self, key: &[u8]) -> bool {
         get_match_len(&self.key, key) > 0
     }
 
+    /// Walks the single path through this node (and its descendants) that
+    /// matches `key`, appending `(accumulated_key, &value)` to `out` for
+    /// every node along that path which carries a value. `path` holds the
+    /// key bytes accumulated so far and is restored before returning, so
+    /// `out` ends up populated in order of increasing key length. Keys
+    /// are returned as raw bytes; callers that want `String` keys are
+    /// responsible for deciding what to do with non-UTF-8 ones.
+    pub(crate) fn collect_prefix_matches<'a>(&'a self,
+                                              key: &[u8],
+                                              path: &mut Vec<u8>,
+                                              out: &mut Vec<(Vec<u8>, &'a T)>) {
+        let match_len = get_match_len(&self.key, key);
+        if match_len != self.key.len() {
+            return;
+        }
+
+        path.extend_from_slice(&self.key);
+
+        if let Some(value) = self.value.as_ref() {
+            out.push((path.clone(), value));
+        }
+
+        if match_len < key.len() {
+            let rest = &key[match_len..];
+            for child in &self.children {
+                if child.prefix_match(rest) {
+                    child.collect_prefix_matches(rest, path, out);
+                    break;
+                }
+            }
+        }
+
+        path.truncate(path.len() - self.key.len());
+    }
+
     /// Inserts a key-value pair into the trie.
     pub fn insert(&mut self, key: Vec<u8>, value: T) {
         // Empty tree, simple set key/value for this node to given key/value.
@@ -140,10 +203,208 @@ impl<T> TrieNode<T> {
         self.add_new_child(key, Some(value));
     }
 
-    /// Deletes the node matching `key` from the trie. If
-    /// `key` does not represent a complete node, i.e. a node
-    /// with a value, nothing happens.
-    pub fn delete(&mut self, key: &[u8]) {}
+    /// Returns a mutable reference to the value slot for `key`, splitting
+    /// or growing this node's subtree along the way if no node for `key`
+    /// exists yet. Used by `Entry` to find the slot an update or
+    /// insertion belongs at with a single descent, rather than one
+    /// descent to look the key up and another to insert it.
+    ///
+    /// Callers must not call this with a `key` that doesn't share a
+    /// common prefix with `self.key` (the same precondition `insert`
+    /// relies on once the trie is non-empty).
+    pub(crate) fn get_or_create_mut(&mut self, key: Vec<u8>) -> &mut Option<T> {
+        let match_len = get_match_len(&self.key, &key);
+
+        if match_len == self.key.len() {
+            if match_len == key.len() {
+                return &mut self.value;
+            }
+
+            return self.get_or_create_child(key[match_len..].to_vec());
+        }
+
+        // Match length was less than the length of this node's key.
+        // Split this node into two separate nodes, same as `insert`.
+        let child_key = self.key[match_len..].to_vec();
+        self.key = self.key[0..match_len].to_vec();
+        let child_value = self.value.take();
+        self.add_new_child(child_key, child_value);
+
+        let rest = key[match_len..].to_vec();
+        if rest.is_empty() {
+            // `key` was a strict prefix of this node's original key, so
+            // the split-off node (now holding just the matched prefix)
+            // is itself the slot for `key` — there's nothing left to
+            // descend into.
+            return &mut self.value;
+        }
+
+        self.get_or_create_child(rest)
+    }
+
+    /// Finds (or creates) the child matching `key` and returns its value
+    /// slot.
+    fn get_or_create_child(&mut self, key: Vec<u8>) -> &mut Option<T> {
+        debug_assert!(!key.is_empty());
+
+        let index = self.children.iter().position(|child| child.prefix_match(&key));
+        match index {
+            Some(i) => self.children[i].get_or_create_mut(key),
+            None => {
+                self.add_new_child(key, None);
+                &mut self.children.last_mut().expect("just inserted").value
+            }
+        }
+    }
+
+    /// Deletes the node matching `key` from the trie, returning the value
+    /// that was removed. If `key` does not represent a complete node, i.e.
+    /// a node with a value, no value is removed and `None` is returned; a
+    /// valueless node left at `key` is still pruned or merged away.
+    ///
+    /// Returns `(removed_value, should_remove_self)`. `should_remove_self`
+    /// tells the caller that this node now has no value and no children,
+    /// and should be dropped from its parent's `children`.
+    pub fn delete(&mut self, key: &[u8]) -> (Option<T>, bool) {
+        let match_len = get_match_len(&self.key, key);
+        if match_len != self.key.len() {
+            // This node's key is not a prefix of `key`, so there is
+            // nothing to delete here.
+            return (None, false);
+        }
+
+        let removed = if match_len == key.len() {
+            // Exact match: this is the node to remove the value from.
+            self.value.take()
+        } else {
+            // Part of `key` remains, so look for it in our children.
+            self.delete_children(&key[match_len..])
+        };
+
+        self.merge_single_child();
+
+        let should_remove_self = self.value.is_none() && self.children.is_empty();
+        (removed, should_remove_self)
+    }
+
+    /// Deletes `key` from this node's children, pruning any child that
+    /// becomes empty as a result.
+    fn delete_children(&mut self, key: &[u8]) -> Option<T> {
+        let mut removed = None;
+        let mut remove_index = None;
+
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if child.prefix_match(key) {
+                let (value, should_remove_child) = child.delete(key);
+                removed = value;
+                if should_remove_child {
+                    remove_index = Some(i);
+                }
+                break;
+            }
+        }
+
+        if let Some(i) = remove_index {
+            self.children.remove(i);
+        }
+
+        removed
+    }
+
+    /// Restores the invariant that an internal node without a value has at
+    /// least two children. If this node has no value and exactly one
+    /// child, that child is merged into this node by concatenating their
+    /// keys.
+    fn merge_single_child(&mut self) {
+        if self.value.is_some() || self.children.len() != 1 {
+            return;
+        }
+
+        let child = self.children.remove(0);
+        self.key.extend(child.key);
+        self.value = child.value;
+        self.children = child.children;
+    }
+
+    /// Depth-first walk of this node and all of its descendants,
+    /// appending `(full_key, &value)` to `out` for every node that
+    /// carries a value. `path` accumulates the key fragments on the way
+    /// down and is restored before returning. Keys are returned as raw
+    /// bytes; callers that want `String` keys are responsible for
+    /// deciding what to do with non-UTF-8 ones.
+    pub(crate) fn collect_entries<'a>(&'a self, path: &mut Vec<u8>, out: &mut Vec<(Vec<u8>, &'a T)>) {
+        path.extend_from_slice(&self.key);
+
+        if let Some(value) = self.value.as_ref() {
+            out.push((path.clone(), value));
+        }
+
+        for child in &self.children {
+            child.collect_entries(path, out);
+        }
+
+        path.truncate(path.len() - self.key.len());
+    }
+}
+
+#[cfg(feature = "merkle")]
+impl<T: AsRef<[u8]>> TrieNode<T> {
+    /// Recomputes this node's digest: the hash of its key fragment, its
+    /// value (if any), and the ordered digests of its children.
+    pub(crate) fn digest<H: Hasher>(&self) -> [u8; 32] {
+        let mut buf = self.key.clone();
+
+        if let Some(value) = self.value.as_ref() {
+            buf.extend_from_slice(&H::hash(value.as_ref()));
+        }
+
+        for child in &self.children {
+            buf.extend_from_slice(&child.digest::<H>());
+        }
+
+        H::hash(&buf)
+    }
+
+    /// Builds an inclusion proof for `key`, walking down to the matching
+    /// node and recording witness data at every level on the way back up.
+    /// Returns `None` if `key` is not present.
+    pub(crate) fn prove<H: Hasher>(&self, key: &[u8]) -> Option<MerkleProof> {
+        let match_len = get_match_len(&self.key, key);
+        if match_len != self.key.len() {
+            return None;
+        }
+
+        if match_len == key.len() {
+            self.value.as_ref()?;
+            let child_digests = self.children.iter().map(|child| child.digest::<H>()).collect();
+            return Some(MerkleProof {
+                nodes: vec![ProofNode {
+                                key: self.key.clone(),
+                                value_digest: None,
+                                child_digests,
+                                path_child_index: None,
+                            }],
+            });
+        }
+
+        let rest = &key[match_len..];
+        for (i, child) in self.children.iter().enumerate() {
+            if child.prefix_match(rest) {
+                let mut proof = child.prove::<H>(rest)?;
+                let child_digests = self.children.iter().map(|c| c.digest::<H>()).collect();
+                let value_digest = self.value.as_ref().map(|v| H::hash(v.as_ref()));
+                proof.nodes.push(ProofNode {
+                    key: self.key.clone(),
+                    value_digest,
+                    child_digests,
+                    path_child_index: Some(i),
+                });
+                return Some(proof);
+            }
+        }
+
+        None
+    }
 }
 
 #[cfg(test)]
@@ -213,4 +474,50 @@ mod test {
         assert_eq!(trie.get(b"/1"), Some(&"Data"));
         assert_eq!(trie.get(b"/2"), Some(&"Data2"));
     }
+
+    #[test]
+    fn delete_split_point() {
+        let mut trie = TrieNode::new();
+        trie.insert(b"/1".to_vec(), "Data");
+        trie.insert(b"/2".to_vec(), "Data2");
+
+        let (removed, should_remove_self) = trie.delete(b"/1");
+        assert_eq!(removed, Some("Data"));
+        assert!(!should_remove_self);
+
+        assert_eq!(trie.get(b"/1"), None);
+        assert_eq!(trie.get(b"/2"), Some(&"Data2"));
+    }
+
+    #[test]
+    fn delete_merges_leaf() {
+        let mut trie = TrieNode::new();
+        trie.insert(b"/1".to_vec(), "Data");
+        trie.insert(b"/2".to_vec(), "Data2");
+
+        let (removed, should_remove_self) = trie.delete(b"/2");
+        assert_eq!(removed, Some("Data2"));
+        assert!(!should_remove_self);
+
+        // The split node had only one remaining child, so it should have
+        // merged back into a single "/1" node.
+        let trie2 = TrieNode {
+            key: b"/1".to_vec(),
+            value: Some("Data"),
+            children: Vec::new(),
+        };
+        assert_eq!(trie, trie2);
+        assert_eq!(trie.get(b"/1"), Some(&"Data"));
+    }
+
+    #[test]
+    fn delete_missing_key() {
+        let mut trie = TrieNode::new();
+        trie.insert(b"/1".to_vec(), "Data");
+
+        let (removed, should_remove_self) = trie.delete(b"/2");
+        assert_eq!(removed, None);
+        assert!(!should_remove_self);
+        assert_eq!(trie.get(b"/1"), Some(&"Data"));
+    }
 }